@@ -0,0 +1,415 @@
+//! Parsing and scoring for football league results.
+//!
+//! A line like `Lions 3, Snakes 3` parses into a [`Match`] via `FromStr`,
+//! and a [`LeagueTable`] turns a stream of matches into ranked standings.
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+// Some alias to make the code more readable.
+pub type TeamName = String;
+
+// A value together with the byte range of the input line it came from, so
+// that a failure further down the pipeline can still point at the exact
+// text that caused it.
+#[derive(PartialEq, Debug)]
+struct Spanned<T> {
+    value: T,
+    span: Range<usize>,
+}
+
+impl<T: PartialOrd> PartialOrd for Spanned<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+// Need to derive impls of PartialEq and Debug for testing.
+#[derive(PartialOrd, PartialEq, Debug)]
+struct Tokens {
+    // Name and score, each carrying the span it was lexed from.
+    team1: (Spanned<TeamName>, Spanned<u32>),
+    team2: (Spanned<TeamName>, Spanned<u32>),
+}
+
+/// The outcome of a single match between two teams.
+#[derive(PartialOrd, PartialEq, Debug)]
+pub enum Match {
+    // Winning team, losing team
+    Win { won: TeamName, lost: TeamName },
+    Draw(TeamName, TeamName),
+}
+
+/// A `Match` failed to parse. Carries the exact byte span of the input
+/// responsible, so a caller can point back at precisely what was wrong.
+#[derive(PartialEq, Debug)]
+pub struct ParseMatchError {
+    pub span: Range<usize>,
+    pub message: String,
+}
+
+impl ParseMatchError {
+    /// Render this error against the line it came from: the line itself,
+    /// followed by a caret underline pointing at the offending span, e.g.:
+    ///   Lions three, Snakes 3
+    ///         ^^^^^ score is not a non-negative integer
+    pub fn render(&self, line: &str) -> String {
+        let width = (self.span.end - self.span.start).max(1);
+        format!(
+            "{line}\n{pad}{carets} {message}\n",
+            line = line,
+            pad = " ".repeat(self.span.start),
+            carets = "^".repeat(width),
+            message = self.message,
+        )
+    }
+}
+
+impl fmt::Display for ParseMatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ParseMatchError {}
+
+impl FromStr for Match {
+    type Err = ParseMatchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        lex(s).map(parse)
+    }
+}
+
+/// Running standings for a league, built up one match at a time.
+#[derive(Default)]
+pub struct LeagueTable {
+    scores: HashMap<TeamName, u32>,
+}
+
+impl LeagueTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply a match's result: 3 points for a win, 1 each for a draw. A
+    /// losing team is still recorded, at 0 points, so it appears in the
+    /// standings.
+    pub fn record(&mut self, m: Match) {
+        match m {
+            Match::Win { won, lost } => {
+                self.scores
+                    .entry(won)
+                    .and_modify(|score| *score += 3)
+                    .or_insert(3);
+                self.scores.entry(lost).or_insert(0);
+            }
+            Match::Draw(team1, team2) => {
+                self.scores
+                    .entry(team1)
+                    .and_modify(|score| *score += 1)
+                    .or_insert(1);
+                self.scores
+                    .entry(team2)
+                    .and_modify(|score| *score += 1)
+                    .or_insert(1);
+            }
+        }
+    }
+
+    /// Teams ranked by score, descending, ties broken alphabetically.
+    pub fn standings(&self) -> Vec<(u32, TeamName)> {
+        let mut standings = self
+            .scores
+            .iter()
+            .map(|(team, score)| (*score, team.clone()))
+            .collect::<Vec<_>>();
+
+        standings.sort_by(|a, b| {
+            if a.0 == b.0 {
+                // If scores are equal, sort alphabetically by team name.
+                a.1.cmp(&b.1)
+            } else {
+                // Otherwise, sort by score, descending.
+                b.0.cmp(&a.0)
+            }
+        });
+
+        standings
+    }
+}
+
+impl fmt::Display for LeagueTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (line, (score, team)) in self.standings().into_iter().enumerate() {
+            // Choose the correct suffix for the score.
+            let unit = if score == 1 { "pt" } else { "pts" };
+            writeln!(f, "{}. {} {} {}", line + 1, team, score, unit)?;
+        }
+        Ok(())
+    }
+}
+
+fn lex(string: &str) -> Result<Tokens, ParseMatchError> {
+    // Find the separator by walking the line rather than trusting a single
+    // `split`: this is what lets us tell "no comma at all" apart from
+    // "too many commas", each with its own message and span.
+    let commas: Vec<usize> = string
+        .char_indices()
+        .filter(|&(_, c)| c == ',')
+        .map(|(i, _)| i)
+        .collect();
+
+    let comma = match commas[..] {
+        [] => {
+            return Err(ParseMatchError {
+                span: 0..string.len(),
+                message: "only one team on line".to_string(),
+            })
+        }
+        [comma] => comma,
+        [_, second, ..] => {
+            return Err(ParseMatchError {
+                span: second..string.len(),
+                message: "extra tokens after second score".to_string(),
+            })
+        }
+    };
+
+    let team1 = scan_team_result(string, 0..comma)?;
+    let team2 = scan_team_result(string, comma + 1..string.len())?;
+
+    Ok(Tokens { team1, team2 })
+}
+
+// Scans one `team, score` half of a line, character by character, tracking
+// the byte offset of every word. A run of whitespace-separated words forms
+// the team name (internal whitespace collapsed to a single space), and the
+// last word must be the score.
+fn scan_team_result(
+    string: &str,
+    range: Range<usize>,
+) -> Result<(Spanned<TeamName>, Spanned<u32>), ParseMatchError> {
+    let mut words: Vec<Spanned<&str>> = vec![];
+    let mut word_start = None;
+    let mut pos = range.start;
+
+    for (i, c) in string[range.clone()].char_indices() {
+        let i = range.start + i;
+        if c.is_whitespace() {
+            if let Some(start) = word_start.take() {
+                words.push(Spanned {
+                    value: &string[start..i],
+                    span: start..i,
+                });
+            }
+        } else if word_start.is_none() {
+            word_start = Some(i);
+        }
+        pos = i + c.len_utf8();
+    }
+    if let Some(start) = word_start {
+        words.push(Spanned {
+            value: &string[start..pos],
+            span: start..pos,
+        });
+    }
+
+    let (score_word, team_words) = match words.split_last() {
+        Some(split) => split,
+        None => {
+            return Err(ParseMatchError {
+                span: range,
+                message: "expected a team name and score".to_string(),
+            })
+        }
+    };
+
+    if team_words.is_empty() {
+        let at = score_word.span.end;
+        return Err(ParseMatchError {
+            span: at..at,
+            message: "missing score".to_string(),
+        });
+    }
+
+    let team_span = team_words.first().unwrap().span.start..team_words.last().unwrap().span.end;
+    let team = team_words.iter().map(|w| w.value).collect::<Vec<_>>().join(" ");
+
+    match score_word.value.parse::<u32>() {
+        Ok(score) => Ok((
+            Spanned {
+                value: team,
+                span: team_span,
+            },
+            Spanned {
+                value: score,
+                span: score_word.span.clone(),
+            },
+        )),
+        Err(_) => Err(ParseMatchError {
+            span: score_word.span.clone(),
+            message: "score is not a non-negative integer".to_string(),
+        }),
+    }
+}
+
+fn parse(tokens: Tokens) -> Match {
+    let (team1, score1) = (tokens.team1.0.value, tokens.team1.1.value);
+    let (team2, score2) = (tokens.team2.0.value, tokens.team2.1.value);
+
+    match score1.cmp(&score2) {
+        // Team 1 won ( team1 > team2 )
+        Ordering::Greater => Match::Win {
+            won: team1,
+            lost: team2,
+        },
+        // Draw ( team1 == team2 )
+        Ordering::Equal => Match::Draw(team1, team2),
+        // Team 2 won ( team1 < team2 )
+        Ordering::Less => Match::Win {
+            won: team2,
+            lost: team1,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lex_line() {
+        let line = "Lions 3, Snakes 3";
+        let expected = Ok(super::Tokens {
+            team1: (
+                Spanned {
+                    value: "Lions".to_string(),
+                    span: 0..5,
+                },
+                Spanned { value: 3, span: 6..7 },
+            ),
+            team2: (
+                Spanned {
+                    value: "Snakes".to_string(),
+                    span: 9..15,
+                },
+                Spanned {
+                    value: 3,
+                    span: 16..17,
+                },
+            ),
+        });
+
+        assert_eq!(super::lex(line), expected);
+    }
+
+    #[test]
+    fn lex_line_spaces() {
+        let line = "Tarantulas 1, FC Awesome 0";
+        let expected = Ok(super::Tokens {
+            team1: (
+                Spanned {
+                    value: "Tarantulas".to_string(),
+                    span: 0..10,
+                },
+                Spanned {
+                    value: 1,
+                    span: 11..12,
+                },
+            ),
+            team2: (
+                Spanned {
+                    value: "FC Awesome".to_string(),
+                    span: 14..24,
+                },
+                Spanned {
+                    value: 0,
+                    span: 25..26,
+                },
+            ),
+        });
+
+        assert_eq!(super::lex(line), expected);
+    }
+
+    #[test]
+    fn lex_invalid_score_points_at_span() {
+        let line = "Lions three, Snakes 3";
+        let err = super::lex(line).unwrap_err();
+
+        assert_eq!(err.span, 6..11);
+        assert_eq!(err.message, "score is not a non-negative integer");
+    }
+
+    #[test]
+    fn lex_missing_comma_reports_only_one_team() {
+        let line = "Lions 3 Snakes 3";
+        let err = super::lex(line).unwrap_err();
+
+        assert_eq!(err.span, 0..line.len());
+        assert_eq!(err.message, "only one team on line");
+    }
+
+    #[test]
+    fn lex_missing_score_reports_at_end_of_team_name() {
+        let line = "Lions, Snakes 3";
+        let err = super::lex(line).unwrap_err();
+
+        assert_eq!(err.span, 5..5);
+        assert_eq!(err.message, "missing score");
+    }
+
+    #[test]
+    fn lex_trailing_comma_reports_extra_tokens() {
+        let line = "Lions 3, Snakes 3,";
+        let err = super::lex(line).unwrap_err();
+
+        assert_eq!(err.span, 17..line.len());
+        assert_eq!(err.message, "extra tokens after second score");
+    }
+
+    #[test]
+    fn match_from_str() {
+        let line = "Lions 4, Snakes 3";
+        let expected = Match::Win {
+            won: "Lions".to_string(),
+            lost: "Snakes".to_string(),
+        };
+
+        assert_eq!(line.parse::<Match>().unwrap(), expected);
+    }
+
+    #[test]
+    fn match_from_str_draw() {
+        let line = "Lions 3, Snakes 3";
+        let expected = Match::Draw("Lions".to_string(), "Snakes".to_string());
+
+        assert_eq!(line.parse::<Match>().unwrap(), expected);
+    }
+
+    #[test]
+    fn league_table_standings_and_display() {
+        let mut table = LeagueTable::new();
+        for line in [
+            "Lions 3, Snakes 3",
+            "Tarantulas 1, FC Awesome 0",
+            "Lions 1, FC Awesome 1",
+            "Tarantulas 3, Snakes 1",
+            "Lions 4, Grouches 0",
+        ] {
+            table.record(line.parse().unwrap());
+        }
+
+        let expected = r#"1. Tarantulas 6 pts
+2. Lions 5 pts
+3. FC Awesome 1 pt
+4. Snakes 1 pt
+5. Grouches 0 pts
+"#;
+
+        assert_eq!(table.to_string(), expected);
+    }
+}